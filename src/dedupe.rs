@@ -0,0 +1,70 @@
+#[cfg(feature = "dedupe")]
+use std::any::Any;
+#[cfg(feature = "dedupe")]
+use std::cell::RefCell;
+#[cfg(feature = "dedupe")]
+use std::collections::HashMap;
+#[cfg(feature = "dedupe")]
+use std::future::Future;
+#[cfg(feature = "dedupe")]
+use std::pin::Pin;
+
+#[cfg(feature = "dedupe")]
+use futures::future::{FutureExt, Shared};
+
+#[cfg(feature = "dedupe")]
+use crate::identity::request_key;
+use crate::Request;
+
+#[cfg(feature = "dedupe")]
+type SharedRun<R> =
+    Shared<Pin<Box<dyn Future<Output = Result<<R as Request>::Output, <R as Request>::Error>>>>>;
+
+#[cfg(feature = "dedupe")]
+thread_local! {
+    static INFLIGHT: RefCell<HashMap<u64, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Runs `request.run()`, coalescing concurrent calls for an equal request
+/// value of the same `Request` type into a single in-flight future shared
+/// across all callers, so e.g. three components mounting at once and
+/// requesting the same data only trigger one underlying call
+#[cfg(feature = "dedupe")]
+pub(crate) async fn run<R: Request + 'static>(request: &R) -> Result<R::Output, R::Error> {
+    let map_key = request_key(request);
+
+    let shared = INFLIGHT.with(|inflight| {
+        let mut inflight = inflight.borrow_mut();
+
+        if let Some(shared) = inflight
+            .get(&map_key)
+            .and_then(|existing| existing.downcast_ref::<SharedRun<R>>())
+        {
+            return shared.clone();
+        }
+
+        let request = request.clone();
+        let future: Pin<Box<dyn Future<Output = Result<R::Output, R::Error>>>> =
+            Box::pin(async move { request.run().await });
+        let shared = future.shared();
+
+        inflight.insert(map_key, Box::new(shared.clone()));
+
+        shared
+    });
+
+    let result = shared.await;
+
+    INFLIGHT.with(|inflight| {
+        inflight.borrow_mut().remove(&map_key);
+    });
+
+    result
+}
+
+/// Runs `request.run()` directly; in-flight deduplication requires the
+/// `dedupe` feature
+#[cfg(not(feature = "dedupe"))]
+pub(crate) async fn run<R: Request + 'static>(request: &R) -> Result<R::Output, R::Error> {
+    request.run().await
+}
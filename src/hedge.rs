@@ -0,0 +1,203 @@
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::future::{select, Either};
+use futures::pin_mut;
+use gloo_timers::future::sleep;
+use web_time::Instant;
+
+use crate::{dedupe, Request};
+
+/// Configuration for latency-aware hedged requests
+///
+/// Once at least `min_samples` completions have been recorded for a given
+/// `Request` type, a second identical `run()` is spawned as soon as the
+/// first attempt has been outstanding longer than the configured
+/// `percentile` of recently observed latencies. Whichever future resolves
+/// first wins and the other is dropped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HedgeConfig {
+    /// Percentile (in `0.0..=1.0`) of recent latencies used as the hedge
+    /// threshold, e.g. `0.95` for p95
+    pub percentile: f64,
+    /// Minimum number of recorded samples required before hedging activates
+    pub min_samples: usize,
+    /// Maximum number of recent samples kept per `Request` type
+    pub window: usize,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            percentile: 0.95,
+            min_samples: 50,
+            window: 200,
+        }
+    }
+}
+
+thread_local! {
+    static HISTOGRAMS: RefCell<HashMap<TypeId, Vec<Duration>>> = RefCell::new(HashMap::new());
+}
+
+fn percentile_of(samples: &[Duration], percentile: f64) -> Option<Duration> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+
+    let idx = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+    sorted.get(idx).copied()
+}
+
+fn record_latency<R: 'static>(duration: Duration, window: usize) {
+    HISTOGRAMS.with(|histograms| {
+        let mut histograms = histograms.borrow_mut();
+        let samples = histograms.entry(TypeId::of::<R>()).or_default();
+
+        samples.push(duration);
+
+        if samples.len() > window {
+            samples.remove(0);
+        }
+    });
+}
+
+fn hedge_delay<R: 'static>(config: &HedgeConfig) -> Option<Duration> {
+    HISTOGRAMS.with(|histograms| {
+        let histograms = histograms.borrow();
+        let samples = histograms.get(&TypeId::of::<R>())?;
+
+        if samples.len() < config.min_samples {
+            return None;
+        }
+
+        percentile_of(samples, config.percentile)
+    })
+}
+
+/// Runs `request.run()`, hedging it per `config` if one is given.
+///
+/// Without a hedge config, concurrent calls for an equal request go through
+/// `dedupe::run` and may share a single in-flight future. A hedge
+/// intentionally races two independent attempts, so it bypasses
+/// deduplication and always issues its own calls.
+pub(crate) async fn run<R: Request + 'static>(
+    request: &R,
+    config: Option<&HedgeConfig>,
+) -> Result<R::Output, R::Error> {
+    match config {
+        Some(config) => run_hedged(request, config).await,
+        None => dedupe::run(request).await,
+    }
+}
+
+/// Runs `request.run()`, spawning a second identical attempt once the first
+/// has been outstanding longer than the hedge delay derived from recently
+/// observed latencies of `R`. The latency of whichever attempt completes is
+/// recorded to keep the percentile adaptive.
+pub(crate) async fn run_hedged<R: Request + 'static>(
+    request: &R,
+    config: &HedgeConfig,
+) -> Result<R::Output, R::Error> {
+    let primary_start = Instant::now();
+
+    let primary = request.run();
+    pin_mut!(primary);
+
+    // Recorded latency is always the winning attempt's own run time, not
+    // wall-clock since `run_hedged` started: a hedge win timed from
+    // `primary_start` would include the hedge delay plus the primary's
+    // aborted wait, inflating the histogram and pushing the hedge
+    // threshold up every time hedging actually helps.
+    let (result, elapsed) = match hedge_delay::<R>(config) {
+        Some(delay) => {
+            let timer = sleep(delay);
+            pin_mut!(timer);
+
+            match select(primary, timer).await {
+                Either::Left((result, _)) => (result, primary_start.elapsed()),
+                Either::Right((_, primary)) => {
+                    let hedge_start = Instant::now();
+                    let hedge = request.run();
+                    pin_mut!(hedge);
+
+                    match select(primary, hedge).await {
+                        Either::Left((result, _)) => (result, primary_start.elapsed()),
+                        Either::Right((result, _)) => (result, hedge_start.elapsed()),
+                    }
+                }
+            }
+        }
+        None => (primary.await, primary_start.elapsed()),
+    };
+
+    record_latency::<R>(elapsed, config.window);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_of_empty_is_none() {
+        assert_eq!(percentile_of(&[], 0.95), None);
+    }
+
+    #[test]
+    fn percentile_of_single_sample() {
+        let samples = [Duration::from_millis(100)];
+        assert_eq!(
+            percentile_of(&samples, 0.95),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn percentile_of_picks_nearest_rank() {
+        let samples = [
+            Duration::from_millis(10),
+            Duration::from_millis(20),
+            Duration::from_millis(30),
+            Duration::from_millis(40),
+            Duration::from_millis(50),
+        ];
+
+        assert_eq!(
+            percentile_of(&samples, 0.0),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(
+            percentile_of(&samples, 1.0),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(
+            percentile_of(&samples, 0.5),
+            Some(Duration::from_millis(30))
+        );
+    }
+
+    #[test]
+    fn percentile_of_sorts_unordered_samples() {
+        let samples = [
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+            Duration::from_millis(30),
+        ];
+
+        assert_eq!(
+            percentile_of(&samples, 0.0),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(
+            percentile_of(&samples, 1.0),
+            Some(Duration::from_millis(50))
+        );
+    }
+}
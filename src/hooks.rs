@@ -1,16 +1,31 @@
 #[cfg(feature = "cache")]
 use crate::CachableRequest;
-use crate::Request;
+#[cfg(feature = "serde")]
+use crate::HydrationRegistry;
+use crate::{hedge, retry, HedgeConfig, Request, RetryPolicy};
+#[cfg(feature = "cache")]
+use crate::{invalidation, CacheKey, InvalidationBus};
+
+use std::time::Duration;
 
+use gloo_timers::callback::Interval;
+use wasm_bindgen_futures::spawn_local;
+#[cfg(feature = "cache")]
+use web_time::Instant;
 use yew::prelude::*;
 use yew::suspense::SuspensionResult;
 #[cfg(feature = "cache")]
-use yewdux::prelude::use_store_value;
+use yewdux::prelude::{use_dispatch, use_store_value};
 
 /// Use API Options
 ///
-/// You may specify dependencies which force the request to be reevaluated
-/// and a handler which is called every time a request is ran
+/// You may specify dependencies which force the request to be reevaluated,
+/// a handler which is called every time a request is ran, a hedge config
+/// to race a second attempt against requests which run unusually long, a
+/// retry policy applied to failed attempts, and a `refetch_interval` to
+/// keep the result live by periodically rerunning the request in the
+/// background. `stale_time` only applies to the cachable variants, see
+/// [`use_cachable_api_with_options`].
 #[derive(Clone, Debug)]
 pub struct Options<R, D>
 where
@@ -19,6 +34,10 @@ where
 {
     pub deps: Option<D>,
     pub handler: Option<Callback<Result<R::Output, R::Error>, ()>>,
+    pub hedge: Option<HedgeConfig>,
+    pub retry: Option<RetryPolicy<R::Error>>,
+    pub refetch_interval: Option<Duration>,
+    pub stale_time: Option<Duration>,
 }
 
 impl<R, D> Default for Options<R, D>
@@ -30,6 +49,10 @@ where
         Self {
             deps: None,
             handler: None,
+            hedge: None,
+            retry: None,
+            refetch_interval: None,
+            stale_time: None,
         }
     }
 }
@@ -44,32 +67,157 @@ pub fn use_api<R: Request + 'static>(request: R) -> SuspensionResult<Result<R::O
 /// The basic api hook which requests data on mount and preserves its
 /// data through out the component lifetime.
 ///
-/// Reruns the request once the dependencies update
+/// Reruns the request once the dependencies update. Under the `serde`
+/// feature, a `HydrationRegistry` provided via context is checked first so
+/// a result already resolved during server rendering is committed without
+/// running the request again during hydration. The hook still suspends
+/// once until that result is committed.
 #[hook]
 pub fn use_api_with_options<R: Request + 'static, D: Clone + PartialEq + 'static>(
     request: R,
     options: Options<R, D>,
 ) -> SuspensionResult<Result<R::Output, R::Error>> {
+    #[cfg(feature = "serde")]
+    let registry = use_context::<HydrationRegistry>();
+
+    // Seeded into `use_future_with_deps_seeded` below rather than returned
+    // early, so every hook in this function is still called on the render
+    // that consumes the hydration entry as well as on every one after it.
+    #[cfg(feature = "serde")]
+    let hydrated = registry
+        .as_ref()
+        .and_then(|registry| registry.take(&request));
+    #[cfg(not(feature = "serde"))]
+    let hydrated: Option<R::Output> = None;
+
+    let poll_request = request.clone();
+    let refetch_interval = options.refetch_interval;
+    let poll_hedge = options.hedge.clone();
+    let poll_retry = options.retry.clone();
+    let poll_handler = options.handler.clone();
+
+    // Bumped every time the future driving `result` is recreated for new
+    // deps, so a background refetch started against a stale generation can
+    // notice it's been superseded and avoid clobbering a newer commit.
+    let generation = use_mut_ref(|| 0u32);
+    let poll_generation = generation.clone();
+
     let deps = (request, options.deps);
 
-    let result = inner::use_future_with_deps(
-        |deps| async move {
-            let result = deps.0.run().await;
+    let result = inner::use_future_with_deps_seeded(
+        {
+            let generation = generation.clone();
 
-            if let Some(ref handler) = options.handler {
-                handler.emit(result.to_owned());
-            }
+            move |deps| {
+                let next_generation = generation.borrow().wrapping_add(1);
+                *generation.borrow_mut() = next_generation;
 
-            if let Ok(ref data) = result {
-                R::store(data.to_owned());
-            }
+                async move {
+                    let result = match options.retry {
+                        Some(ref policy) => {
+                            retry::run_with_retry(&deps.0, policy, options.hedge.as_ref()).await
+                        }
+                        None => hedge::run(&deps.0, options.hedge.as_ref()).await,
+                    };
+
+                    if let Some(ref handler) = options.handler {
+                        handler.emit(result.to_owned());
+                    }
+
+                    if let Ok(ref data) = result {
+                        R::store(data.to_owned());
 
-            result
+                        #[cfg(feature = "serde")]
+                        if let Some(ref registry) = registry {
+                            registry.store(&deps.0, data);
+                        }
+                    }
+
+                    result
+                }
+            }
         },
         deps,
+        hydrated.map(Ok),
     )?;
 
-    Ok((*result).to_owned())
+    // Once resolved once, the latest result is kept visible on screen while a
+    // background refetch (if any) is in flight, instead of suspending again
+    let latest = use_state(|| (*result).to_owned());
+
+    // `result` already reflects newly resolved deps as soon as they land,
+    // but `set` only takes effect for the *next* render — so without this,
+    // the render that first observes new data would still return the old
+    // `latest` for one frame. Reconcile synchronously and return `current`
+    // below instead of dereferencing `latest` again. The comparison is
+    // against the last output seen from `result`, not against `latest`, so
+    // a newer background refetch result in `latest` isn't reverted.
+    let resolved = (*result).to_owned();
+    let last_resolved = use_mut_ref(|| resolved.clone());
+    let resolved_changed = resolved != *last_resolved.borrow();
+    let current = if resolved_changed {
+        *last_resolved.borrow_mut() = resolved.clone();
+        latest.set(resolved.clone());
+        resolved
+    } else {
+        (*latest).to_owned()
+    };
+
+    {
+        let latest = latest.clone();
+
+        use_effect_with(
+            (poll_request.clone(), refetch_interval),
+            move |(poll_request, refetch_interval)| {
+                let interval = refetch_interval.map(|refetch_interval| {
+                    let poll_request = poll_request.clone();
+
+                    Interval::new(refetch_interval.as_millis() as u32, move || {
+                        let poll_request = poll_request.clone();
+                        let latest = latest.clone();
+                        let poll_hedge = poll_hedge.clone();
+                        let poll_retry = poll_retry.clone();
+                        let poll_handler = poll_handler.clone();
+                        let poll_generation = poll_generation.clone();
+                        let started_at_generation = *poll_generation.borrow();
+
+                        spawn_local(async move {
+                            let result = match poll_retry {
+                                Some(ref policy) => {
+                                    retry::run_with_retry(
+                                        &poll_request,
+                                        policy,
+                                        poll_hedge.as_ref(),
+                                    )
+                                    .await
+                                }
+                                None => hedge::run(&poll_request, poll_hedge.as_ref()).await,
+                            };
+
+                            if let Some(ref handler) = poll_handler {
+                                handler.emit(result.to_owned());
+                            }
+
+                            if let Ok(ref data) = result {
+                                R::store(data.to_owned());
+                            }
+
+                            // Drop the result if deps moved on to a newer
+                            // generation while this poll was in flight, so it
+                            // can't clobber a fresher result with stale data
+                            if *poll_generation.borrow() == started_at_generation {
+                                latest.set(result);
+                            }
+                        });
+                    })
+                });
+
+                move || drop(interval)
+            },
+        );
+    }
+
+    Ok(current)
 }
 
 /// A lazy api response which you can trigger through the `run` callback
@@ -133,7 +281,12 @@ pub fn use_api_dynamic_with_options<R: Request + 'static, D: Clone + PartialEq +
                 return None;
             };
 
-            let result = request.run().await;
+            let result = match options.retry {
+                Some(ref policy) => {
+                    retry::run_with_retry(request, policy, options.hedge.as_ref()).await
+                }
+                None => hedge::run(request, options.hedge.as_ref()).await,
+            };
 
             if let Some(ref handler) = options.handler {
                 handler.emit(result.to_owned());
@@ -172,6 +325,10 @@ pub fn use_cachable_api<R: Request + CachableRequest + 'static>(
 }
 
 /// Use the locally cached data instead of running the api request if possible
+///
+/// If `stale_time` is set, a cache hit older than it is still returned
+/// immediately but revalidated in the background, keeping subsequent reads
+/// fresh without ever suspending the component
 #[cfg(feature = "cache")]
 #[hook]
 pub fn use_cachable_api_with_options<
@@ -182,24 +339,101 @@ pub fn use_cachable_api_with_options<
     options: Options<R, D>,
 ) -> SuspensionResult<Result<R::Output, R::Error>> {
     let store = use_store_value::<R::Store>();
-    let deps = (request, options.deps);
+    let last_fetched = use_state(|| Option::<Instant>::None);
+    let invalidation_epoch = use_store_value::<InvalidationBus>().epoch(&request.cache_key());
+    let deps = (request, options.deps, invalidation_epoch);
+
+    // Bumped every time the future driving `result` is recreated for new
+    // deps, so a stale-revalidation started against a previous generation
+    // can notice it's been superseded and avoid clobbering fresher data.
+    let generation = use_mut_ref(|| 0u32);
+
+    // The invalidation epoch observed the last time this future was
+    // created. A bump since then (as opposed to the initial mount, where
+    // there's nothing to compare against) means `use_api_mutation` marked
+    // this cache key dirty, so the cached value must not be re-served
+    // as-is even if it isn't old enough to be time-based stale.
+    let last_epoch = use_mut_ref(|| Option::<u64>::None);
+
     let result = inner::use_future_with_deps(
-        |deps| async move {
-            if let Some(cache) = deps.0.load(store) {
-                return Ok(cache);
-            }
+        {
+            let generation = generation.clone();
+            let last_epoch = last_epoch.clone();
+
+            move |deps| {
+                let next_generation = generation.borrow().wrapping_add(1);
+                *generation.borrow_mut() = next_generation;
+                let generation = generation.clone();
+
+                let invalidated = last_epoch.borrow().map_or(false, |seen| seen != deps.2);
+                *last_epoch.borrow_mut() = Some(deps.2);
+
+                async move {
+                    if !invalidated {
+                        if let Some(cache) = deps.0.load(store) {
+                            let stale = options.stale_time.map_or(false, |stale_time| {
+                                last_fetched
+                                    .as_ref()
+                                    .map_or(true, |fetched_at| fetched_at.elapsed() > stale_time)
+                            });
+
+                            // Serve the stale cache immediately, revalidating in the
+                            // background; `R::store` writes the fresh result through
+                            // the yewdux store, where the next `load` of this request
+                            // picks it up. This hook keeps returning the value served
+                            // here until its deps change.
+                            if stale {
+                                let request = deps.0.clone();
+                                let last_fetched = last_fetched.clone();
+                                let hedge = options.hedge.clone();
+                                let retry = options.retry.clone();
+                                let generation = generation.clone();
+                                let started_at_generation = *generation.borrow();
+
+                                spawn_local(async move {
+                                    let result = match retry {
+                                        Some(ref policy) => {
+                                            retry::run_with_retry(&request, policy, hedge.as_ref())
+                                                .await
+                                        }
+                                        None => hedge::run(&request, hedge.as_ref()).await,
+                                    };
+
+                                    // Drop the result if deps moved on to a newer
+                                    // generation while this revalidation was in
+                                    // flight, so it can't clobber fresher data
+                                    if let Ok(ref data) = result {
+                                        if *generation.borrow() == started_at_generation {
+                                            R::store(data.to_owned());
+                                            last_fetched.set(Some(Instant::now()));
+                                        }
+                                    }
+                                });
+                            }
+
+                            return Ok(cache);
+                        }
+                    }
 
-            let result = deps.0.run().await;
+                    let result = match options.retry {
+                        Some(ref policy) => {
+                            retry::run_with_retry(&deps.0, policy, options.hedge.as_ref()).await
+                        }
+                        None => hedge::run(&deps.0, options.hedge.as_ref()).await,
+                    };
 
-            if let Some(ref handler) = options.handler {
-                handler.emit(result.to_owned());
-            }
+                    if let Some(ref handler) = options.handler {
+                        handler.emit(result.to_owned());
+                    }
 
-            if let Ok(ref data) = result {
-                R::store(data.to_owned());
-            }
+                    if let Ok(ref data) = result {
+                        R::store(data.to_owned());
+                        last_fetched.set(Some(Instant::now()));
+                    }
 
-            result
+                    result
+                }
+            }
         },
         deps,
     )?;
@@ -270,7 +504,12 @@ pub fn use_cachable_api_dynamic_with_options<
                 return Some(Ok(cache));
             }
 
-            let result = request.run().await;
+            let result = match options.retry {
+                Some(ref policy) => {
+                    retry::run_with_retry(request, policy, options.hedge.as_ref()).await
+                }
+                None => hedge::run(request, options.hedge.as_ref()).await,
+            };
 
             if let Some(ref handler) = options.handler {
                 handler.emit(result.to_owned());
@@ -299,6 +538,97 @@ pub fn use_cachable_api_dynamic_with_options<
     DynLazyResponse { run, data }
 }
 
+/// The status of an imperative mutation triggered through `use_api_mutation`
+#[cfg(feature = "cache")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MutationStatus<R: Request + 'static> {
+    Idle,
+    Pending,
+    Done(Result<R::Output, R::Error>),
+}
+
+/// Options for `use_api_mutation_with_options`
+#[cfg(feature = "cache")]
+#[derive(Clone, Debug)]
+pub struct MutationOptions<R: Request + 'static> {
+    pub handler: Option<Callback<Result<R::Output, R::Error>, ()>>,
+    /// Cache keys to bump the invalidation epoch of on a successful mutation,
+    /// forcing dependent `use_cachable_api` subscribers to revalidate
+    pub invalidates: Vec<CacheKey>,
+}
+
+#[cfg(feature = "cache")]
+impl<R: Request + 'static> Default for MutationOptions<R> {
+    fn default() -> Self {
+        Self {
+            handler: None,
+            invalidates: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "cache")]
+pub struct MutationResponse<R: Request + 'static> {
+    pub run: Callback<R, ()>,
+    pub status: MutationStatus<R>,
+}
+
+/// Imperatively runs a `Request`, e.g. for a POST/PUT/DELETE-style mutation,
+/// and writes its result through `R::store` on success
+#[cfg(feature = "cache")]
+#[hook]
+pub fn use_api_mutation<R: Request + 'static>() -> MutationResponse<R> {
+    use_api_mutation_with_options::<R>(Default::default())
+}
+
+/// Imperatively runs a `Request`, e.g. for a POST/PUT/DELETE-style mutation,
+/// and writes its result through `R::store` on success.
+///
+/// Bumps the invalidation epoch of every key in `options.invalidates` once
+/// the mutation succeeds, so dependent `use_cachable_api` subscribers of
+/// those keys re-run their request instead of serving stale cached entities.
+#[cfg(feature = "cache")]
+#[hook]
+pub fn use_api_mutation_with_options<R: Request + 'static>(
+    options: MutationOptions<R>,
+) -> MutationResponse<R> {
+    let status = use_state(|| MutationStatus::<R>::Idle);
+    let invalidation_bus = use_dispatch::<InvalidationBus>();
+
+    let run = {
+        let status = status.clone();
+
+        Callback::from(move |request: R| {
+            let status = status.clone();
+            let handler = options.handler.clone();
+            let invalidates = options.invalidates.clone();
+            let invalidation_bus = invalidation_bus.clone();
+
+            status.set(MutationStatus::Pending);
+
+            spawn_local(async move {
+                let result = request.run().await;
+
+                if let Some(ref handler) = handler {
+                    handler.emit(result.to_owned());
+                }
+
+                if let Ok(ref data) = result {
+                    R::store(data.to_owned());
+                    invalidation::invalidate(&invalidation_bus, &invalidates);
+                }
+
+                status.set(MutationStatus::Done(result));
+            });
+        })
+    };
+
+    MutationResponse {
+        run,
+        status: (*status).to_owned(),
+    }
+}
+
 /// from yew@next
 mod inner {
     use std::borrow::Borrow;
@@ -343,6 +673,31 @@ mod inner {
 
     #[hook]
     pub fn use_future_with_deps<F, D, T, O>(f: F, deps: D) -> SuspensionResult<UseFutureHandle<O>>
+    where
+        F: FnOnce(Rc<D>) -> T,
+        T: Future<Output = O> + 'static,
+        O: 'static,
+        D: PartialEq + 'static,
+    {
+        use_future_with_deps_seeded(f, deps, None)
+    }
+
+    /// Like `use_future_with_deps`, but if `seed` is `Some` on the render
+    /// that first constructs the future for a given `deps`, that value is
+    /// committed as the result instead of calling `f` at all. Like any
+    /// future's output, it is committed once the suspension resumes, so the
+    /// render that constructs it still suspends.
+    ///
+    /// This must still call the same hooks on every render regardless of
+    /// whether a seed is present, so a caller deciding whether to seed
+    /// (e.g. from a hydration registry) can't turn this into a conditional
+    /// hook call.
+    #[hook]
+    pub fn use_future_with_deps_seeded<F, D, T, O>(
+        f: F,
+        deps: D,
+        seed: Option<O>,
+    ) -> SuspensionResult<UseFutureHandle<O>>
     where
         F: FnOnce(Rc<D>) -> T,
         T: Future<Output = O> + 'static,
@@ -363,6 +718,16 @@ mod inner {
                     // As long as less than 2**32 futures are in flight wrapping_add is fine
                     (*latest_id).set(self_id);
                     let deps = Rc::new(deps);
+
+                    if let Some(seed) = seed {
+                        let suspension = Suspension::from_future(async move {
+                            if latest_id.get() == self_id {
+                                output.set(Some(seed));
+                            }
+                        });
+                        return (suspension, deps);
+                    }
+
                     let task = f(deps.clone());
                     let suspension = Suspension::from_future(async move {
                         let result = task.await;
@@ -0,0 +1,27 @@
+use std::any::type_name;
+use std::hash::{Hash, Hasher};
+
+use crate::Request;
+
+/// A stable identity for a request value, combining its type name with
+/// its field values via `Hash`
+///
+/// Used to coalesce concurrent calls for an equal request
+/// ([`crate::dedupe`]) and to key resolved results for hydration
+/// ([`crate::ssr`]). Deriving this from `Hash` rather than `Debug` means two
+/// requests that are `!=` by `PartialEq` can't collide just because a
+/// hand-written `Debug` impl happens to render them identically.
+///
+/// The type is keyed by its name rather than its `TypeId`, which differs
+/// between a server build and a wasm client build. `Hash` writes `usize`
+/// and `isize` values and collection lengths at the target's pointer width,
+/// so a request holding such fields gets different keys on a 64-bit server
+/// and a wasm32 client.
+pub(crate) fn request_key<R: Request + 'static>(request: &R) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+
+    type_name::<R>().hash(&mut hasher);
+    request.hash(&mut hasher);
+
+    hasher.finish()
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use yewdux::prelude::Dispatch;
+use yewdux::store::Store;
+
+/// Identifies a slice of cached state that can be invalidated through
+/// [`crate::use_api_mutation`]
+pub type CacheKey = String;
+
+/// Tracks a monotonically increasing epoch per [`CacheKey`]
+///
+/// `use_cachable_api_with_options` includes the epoch of its request's
+/// cache key in its dependencies, so bumping it via [`invalidate`] forces a
+/// revalidation of every subscriber reading that key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InvalidationBus {
+    epochs: HashMap<CacheKey, u64>,
+}
+
+impl InvalidationBus {
+    /// The current epoch for `key`, `0` if it has never been invalidated
+    pub fn epoch(&self, key: &CacheKey) -> u64 {
+        self.epochs.get(key).copied().unwrap_or(0)
+    }
+}
+
+impl Store for InvalidationBus {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn should_notify(&self, old: &Self) -> bool {
+        self != old
+    }
+}
+
+/// Bumps the epoch of every key in `keys`, forcing dependent
+/// `use_cachable_api_with_options` subscribers to revalidate
+pub(crate) fn invalidate(dispatch: &Dispatch<InvalidationBus>, keys: &[CacheKey]) {
+    dispatch.reduce_mut(|bus| {
+        for key in keys {
+            *bus.epochs.entry(key.clone()).or_insert(0) += 1;
+        }
+    });
+}
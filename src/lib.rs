@@ -1,36 +1,72 @@
+mod dedupe;
+mod hedge;
 mod hooks;
+mod identity;
+#[cfg(feature = "cache")]
+mod invalidation;
+mod retry;
+#[cfg(feature = "serde")]
+mod ssr;
 
 use async_trait::async_trait;
 
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 #[cfg(feature = "cache")]
 use std::rc::Rc;
 #[cfg(feature = "cache")]
 use yewdux::store::Store;
 
+pub use hedge::HedgeConfig;
 pub use hooks::*;
+#[cfg(feature = "cache")]
+pub use invalidation::{CacheKey, InvalidationBus};
+pub use retry::RetryPolicy;
+#[cfg(feature = "serde")]
+pub use ssr::HydrationRegistry;
 
 pub mod prelude {
-    pub use crate::{hooks::*, Request};
+    pub use crate::{hedge::HedgeConfig, hooks::*, retry::RetryPolicy, Request};
     pub use async_trait::async_trait;
 
+    #[cfg(feature = "cache")]
+    pub use crate::invalidation::{CacheKey, InvalidationBus};
     #[cfg(feature = "cache")]
     pub use crate::CachableRequest;
     #[cfg(feature = "cache")]
     pub use std::rc::Rc;
     #[cfg(feature = "cache")]
     pub use yewdux::store::Store;
+
+    #[cfg(feature = "serde")]
+    pub use crate::ssr::HydrationRegistry;
 }
 
 /// The core request trait which has to be implemented for all handler
 /// which can be executed through the use api hook.
+///
+/// Bound by `Hash` so a request's identity (used to coalesce in-flight
+/// duplicates and to key hydrated results) can be derived from its actual
+/// field values instead of a lossy proxy like its `Debug` representation.
 #[async_trait(?Send)]
-pub trait Request: std::fmt::Debug + PartialEq + Clone {
+pub trait Request: std::fmt::Debug + PartialEq + Clone + std::hash::Hash {
     /// The error which can occur on request failure
     type Error: Clone + std::fmt::Debug + PartialEq + 'static;
 
     /// The output type of a succesful request
+    #[cfg(not(feature = "serde"))]
     type Output: Clone + std::fmt::Debug + PartialEq + 'static;
 
+    /// The output type of a succesful request
+    ///
+    /// Bound by `Serialize + DeserializeOwned` under the `serde` feature so
+    /// resolved results can be stashed into a `HydrationRegistry` and read
+    /// back on hydration
+    #[cfg(feature = "serde")]
+    type Output: Clone + std::fmt::Debug + PartialEq + Serialize + DeserializeOwned + 'static;
+
     /// Run the asynchronous operation responsible for fetching or
     /// computing the requested data
     async fn run(&self) -> Result<Self::Output, Self::Error>;
@@ -48,4 +84,12 @@ pub trait CachableRequest: Request {
 
     /// Optionally extract the requested entity from the yewdux store
     fn load(&self, store: Rc<Self::Store>) -> Option<Self::Output>;
+
+    /// Identifies the slice of cached state this request reads, matched
+    /// against the keys an `use_api_mutation` invalidates. Defaults to the
+    /// request's `Debug` representation; override it if several distinct
+    /// requests should be invalidated together.
+    fn cache_key(&self) -> CacheKey {
+        format!("{self:?}")
+    }
 }
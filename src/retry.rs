@@ -0,0 +1,126 @@
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gloo_timers::future::sleep;
+
+use crate::hedge::{self, HedgeConfig};
+use crate::Request;
+
+/// Retry policy applied to a failed `Request::run`
+///
+/// Attempts are retried with exponential backoff (`base_delay *
+/// multiplier.powi(attempt - 1)`, capped at `max_delay`) plus random jitter
+/// in `[0, delay / 2)`. `retryable` decides, per error, whether another
+/// attempt should be made at all.
+#[derive(Clone)]
+pub struct RetryPolicy<E> {
+    /// Maximum number of attempts, including the first
+    pub max_attempts: usize,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Growth factor applied to the delay on each subsequent retry
+    pub multiplier: f64,
+    /// Upper bound on the computed delay, before jitter
+    pub max_delay: Duration,
+    /// Predicate deciding whether a given error should be retried
+    pub retryable: Rc<dyn Fn(&E) -> bool>,
+}
+
+impl<E> fmt::Debug for RetryPolicy<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("multiplier", &self.multiplier)
+            .field("max_delay", &self.max_delay)
+            .finish()
+    }
+}
+
+impl<E> Default for RetryPolicy<E> {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(10),
+            retryable: Rc::new(|_| true),
+        }
+    }
+}
+
+fn backoff<E>(policy: &RetryPolicy<E>, attempt: u32) -> Duration {
+    let scaled = policy
+        .base_delay
+        .mul_f64(policy.multiplier.powi(attempt as i32 - 1));
+    let capped = scaled.min(policy.max_delay);
+    let jitter = fastrand::f64() * (capped.as_secs_f64() / 2.0);
+
+    capped + Duration::from_secs_f64(jitter)
+}
+
+/// Runs `request.run()` (optionally hedged), retrying on failure according
+/// to `policy` until it succeeds, is exhausted, or hits a non-retryable error
+pub(crate) async fn run_with_retry<R: Request + 'static>(
+    request: &R,
+    policy: &RetryPolicy<R::Error>,
+    hedge: Option<&HedgeConfig>,
+) -> Result<R::Output, R::Error> {
+    let mut attempt = 1;
+
+    loop {
+        match hedge::run(request, hedge).await {
+            Ok(output) => return Ok(output),
+            Err(error) => {
+                if attempt >= policy.max_attempts || !(policy.retryable)(&error) {
+                    return Err(error);
+                }
+
+                sleep(backoff(policy, attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy<()> {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(10),
+            retryable: Rc::new(|_| true),
+        }
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_before_jitter() {
+        let policy = policy();
+
+        // Jitter adds `[0, delay / 2)`, so the computed delay is always in
+        // `[delay, delay * 1.5)`.
+        let first = backoff(&policy, 1);
+        assert!(first >= Duration::from_millis(100) && first < Duration::from_millis(150));
+
+        let second = backoff(&policy, 2);
+        assert!(second >= Duration::from_millis(200) && second < Duration::from_millis(300));
+
+        let third = backoff(&policy, 3);
+        assert!(third >= Duration::from_millis(400) && third < Duration::from_millis(600));
+    }
+
+    #[test]
+    fn backoff_is_capped_at_max_delay() {
+        let mut policy = policy();
+        policy.max_delay = Duration::from_millis(500);
+
+        // attempt 10 would be 100ms * 2^9 = 51200ms uncapped
+        let delay = backoff(&policy, 10);
+        assert!(delay >= Duration::from_millis(500) && delay < Duration::from_millis(750));
+    }
+}
@@ -0,0 +1,53 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::identity::request_key;
+use crate::Request;
+
+/// Registry of resolved request outputs shared between the server render
+/// and the client hydration pass
+///
+/// Provide it via a `ContextProvider<HydrationRegistry>` around the app.
+/// On the server, `use_api_with_options` stashes every successfully
+/// resolved result into the registry keyed by a stable hash of the request;
+/// after rendering, the server integration calls [`HydrationRegistry::drain`]
+/// and serializes the result into the page. On the client, a freshly
+/// constructed registry is seeded with [`HydrationRegistry::from_serialized`]
+/// so `use_api_with_options` commits the stashed result instead of running
+/// the request again; it still suspends once until that result is committed.
+#[derive(Clone, Default, PartialEq)]
+pub struct HydrationRegistry {
+    entries: Rc<RefCell<HashMap<u64, String>>>,
+}
+
+impl HydrationRegistry {
+    /// Builds a registry from the JSON object produced by [`HydrationRegistry::drain`]
+    pub fn from_serialized(json: &str) -> Self {
+        let entries = serde_json::from_str(json).unwrap_or_default();
+
+        Self {
+            entries: Rc::new(RefCell::new(entries)),
+        }
+    }
+
+    /// Serializes the registry's current contents into a JSON object to
+    /// embed in the server-rendered page
+    pub fn drain(&self) -> String {
+        serde_json::to_string(&*self.entries.borrow()).unwrap_or_default()
+    }
+
+    pub(crate) fn store<R: Request + 'static>(&self, request: &R, output: &R::Output) {
+        if let Ok(serialized) = serde_json::to_string(output) {
+            self.entries
+                .borrow_mut()
+                .insert(request_key(request), serialized);
+        }
+    }
+
+    pub(crate) fn take<R: Request + 'static>(&self, request: &R) -> Option<R::Output> {
+        let serialized = self.entries.borrow_mut().remove(&request_key(request))?;
+
+        serde_json::from_str(&serialized).ok()
+    }
+}